@@ -1,23 +1,46 @@
 // buffered_char_reader.rs
 use std::io::{self, BufRead, BufReader, Read};
 
+use crate::escape_decode;
+
 pub struct BufferedCharReader<R: Read> {
     reader: BufReader<R>,
     buf: String,
     pos: usize,
     peeked: Option<char>,
+    decode_escapes: bool,
+    backslash_run: usize,
+    malformed_escapes: usize,
 }
 
 impl<R: Read> BufferedCharReader<R> {
+    #[allow(dead_code)]
     pub fn new(reader: R) -> Self {
+        Self::with_unicode_escapes(reader, true)
+    }
+
+    /// Like [`new`](Self::new), but with explicit control over whether
+    /// `\uXXXX` escapes are decoded on the fly (the CLI's `--no-unicode-escapes`
+    /// opt-out keeps them literal).
+    pub fn with_unicode_escapes(reader: R, decode_escapes: bool) -> Self {
         Self {
             reader: BufReader::with_capacity(4096, reader),
             buf: String::new(),
             pos: 0,
             peeked: None,
+            decode_escapes,
+            backslash_run: 0,
+            malformed_escapes: 0,
         }
     }
 
+    /// How many `\uXXXX` sequences looked like an escape (one-or-more `u`s
+    /// after an unescaped backslash) but didn't decode: bad hex digits, or a
+    /// surrogate half without a matching partner.
+    pub fn malformed_escape_count(&self) -> usize {
+        self.malformed_escapes
+    }
+
     fn fill_buf_if_needed(&mut self) -> io::Result<()> {
         while self.pos >= self.buf.len() {
             self.buf.clear();
@@ -30,10 +53,7 @@ impl<R: Read> BufferedCharReader<R> {
         Ok(())
     }
 
-    pub fn next_char(&mut self) -> io::Result<Option<char>> {
-        if let Some(c) = self.peeked.take() {
-            return Ok(Some(c));
-        }
+    fn read_raw_char(&mut self) -> io::Result<Option<char>> {
         self.fill_buf_if_needed()?;
         if self.pos >= self.buf.len() {
             return Ok(None);
@@ -43,10 +63,106 @@ impl<R: Read> BufferedCharReader<R> {
         Ok(Some(c))
     }
 
+    /// If the reader's `pos` sits right after an unescaped `\`, and what
+    /// follows is a valid escape (see [`escape_decode`]), consume it and
+    /// return the decoded char. A malformed near-miss (looked like an
+    /// escape but wasn't valid) bumps `malformed_escapes` and leaves the
+    /// input untouched so it's read literally instead.
+    fn try_decode_escape(&mut self) -> Option<char> {
+        let rest = &self.buf[self.pos..];
+        match escape_decode::decode(rest) {
+            Some((c, consumed)) => {
+                self.pos += consumed;
+                Some(c)
+            }
+            None => {
+                if escape_decode::looks_like_escape(rest) {
+                    self.malformed_escapes += 1;
+                }
+                None
+            }
+        }
+    }
+
+    fn produce_char(&mut self) -> io::Result<Option<char>> {
+        let c = match self.read_raw_char()? {
+            Some(c) => c,
+            None => return Ok(None),
+        };
+        if self.decode_escapes && c == '\\' && self.backslash_run.is_multiple_of(2) {
+            if let Some(decoded) = self.try_decode_escape() {
+                self.backslash_run = 0;
+                return Ok(Some(decoded));
+            }
+        }
+        self.backslash_run = if c == '\\' { self.backslash_run + 1 } else { 0 };
+        Ok(Some(c))
+    }
+
+    pub fn next_char(&mut self) -> io::Result<Option<char>> {
+        if let Some(c) = self.peeked.take() {
+            return Ok(Some(c));
+        }
+        self.produce_char()
+    }
+
     pub fn peek_char(&mut self) -> io::Result<Option<char>> {
         if self.peeked.is_none() {
-            self.peeked = self.next_char()?;
+            self.peeked = self.produce_char()?;
         }
         Ok(self.peeked)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_all(input: &str) -> (String, usize) {
+        let mut reader = BufferedCharReader::with_unicode_escapes(input.as_bytes(), true);
+        let mut out = String::new();
+        while let Some(c) = reader.next_char().unwrap() {
+            out.push(c);
+        }
+        (out, reader.malformed_escape_count())
+    }
+
+    #[test]
+    fn decodes_a_simple_escape() {
+        let (text, malformed) = read_all("\\u0041");
+        assert_eq!(text, "A");
+        assert_eq!(malformed, 0);
+    }
+
+    #[test]
+    fn escaped_backslash_is_not_an_escape_opener() {
+        let (text, malformed) = read_all("\\\\u0041");
+        assert_eq!(text, "\\\\u0041");
+        assert_eq!(malformed, 0);
+    }
+
+    #[test]
+    fn three_backslashes_still_decode_the_trailing_escape() {
+        let (text, malformed) = read_all("\\\\\\u0041");
+        assert_eq!(text, "\\\\A");
+        assert_eq!(malformed, 0);
+    }
+
+    #[test]
+    fn malformed_escape_is_left_as_literal_text() {
+        let (text, malformed) = read_all("\\u00zz");
+        assert_eq!(text, "\\u00zz");
+        assert_eq!(malformed, 1);
+    }
+
+    #[test]
+    fn decoding_can_be_turned_off() {
+        let mut reader = BufferedCharReader::with_unicode_escapes("\\u0041".as_bytes(), false);
+        let mut out = String::new();
+        while let Some(c) = reader.next_char().unwrap() {
+            out.push(c);
+        }
+        assert_eq!(out, "\\u0041");
+        assert_eq!(reader.malformed_escape_count(), 0);
+    }
+}
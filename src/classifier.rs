@@ -0,0 +1,114 @@
+// classifier.rs
+//! Classifies each comment token by how it sits relative to the code
+//! around it, so downstream tooling can treat a trailing end-of-line
+//! comment differently from a standalone documentation block.
+
+use crate::tokenizer::Token;
+
+/// How a comment sits relative to the code around it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommentStyle {
+    /// No code on any line the comment spans.
+    Isolated,
+    /// Code appears before the comment on its starting line, but not after
+    /// its end on its ending line.
+    Trailing,
+    /// Code appears after the comment's end on its ending line, but not
+    /// before its start on its starting line (e.g. `/* x */ foo(bar);`).
+    Leading,
+    /// Code appears both before the comment's start and after its end on
+    /// the same line (e.g. `foo(/* x */ bar)`).
+    Mixed,
+    /// An isolated comment preceded by two or more blank lines, i.e. one
+    /// used purely for visual layout rather than documenting what follows.
+    BlankLine,
+}
+
+/// Classify every comment token in `tokens` against the code around it in
+/// `input`. Non-comment tokens are skipped; the result has one entry per
+/// comment, in source order.
+pub fn classify(input: &str, tokens: &[Token]) -> Vec<(Token, CommentStyle)> {
+    tokens
+        .iter()
+        .filter(|t| t.kind.is_comment())
+        .map(|&token| {
+            let code_before = has_code_before(input, token.start);
+            let code_after = has_code_after(input, token.start + token.len);
+            let style = match (code_before, code_after) {
+                (true, true) => CommentStyle::Mixed,
+                (true, false) => CommentStyle::Trailing,
+                (false, true) => CommentStyle::Leading,
+                (false, false) if blank_lines_before(input, token.start) >= 2 => CommentStyle::BlankLine,
+                (false, false) => CommentStyle::Isolated,
+            };
+            (token, style)
+        })
+        .collect()
+}
+
+/// Is there a non-whitespace char between the start of `start`'s line and
+/// `start` itself?
+fn has_code_before(input: &str, start: usize) -> bool {
+    let line_start = input[..start].rfind('\n').map_or(0, |i| i + 1);
+    input[line_start..start].chars().any(|c| !c.is_whitespace())
+}
+
+/// Is there a non-whitespace char between `end` and the end of its line?
+fn has_code_after(input: &str, end: usize) -> bool {
+    let line_end = input[end..].find('\n').map_or(input.len(), |i| end + i);
+    input[end..line_end].chars().any(|c| !c.is_whitespace())
+}
+
+/// How many whitespace-only lines immediately precede the line that
+/// `start` sits on.
+fn blank_lines_before(input: &str, start: usize) -> usize {
+    let mut count = 0;
+    let mut line_start = input[..start].rfind('\n').map_or(0, |i| i + 1);
+    while line_start > 0 {
+        let prev_line_end = line_start - 1;
+        let prev_line_start = input[..prev_line_end].rfind('\n').map_or(0, |i| i + 1);
+        if input[prev_line_start..prev_line_end].trim().is_empty() {
+            count += 1;
+            line_start = prev_line_start;
+        } else {
+            break;
+        }
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tokenizer::tokenize;
+
+    fn styles(input: &str) -> Vec<CommentStyle> {
+        let tokens: Vec<_> = tokenize(input).collect();
+        classify(input, &tokens).into_iter().map(|(_, style)| style).collect()
+    }
+
+    #[test]
+    fn isolated_comment_has_no_code_on_its_line() {
+        assert_eq!(styles("    // standalone\nfoo();\n"), [CommentStyle::Isolated]);
+    }
+
+    #[test]
+    fn trailing_comment_follows_code_on_the_same_line() {
+        assert_eq!(styles("foo(); // trailing\n"), [CommentStyle::Trailing]);
+    }
+
+    #[test]
+    fn leading_comment_precedes_code_on_the_same_line() {
+        assert_eq!(styles("/* x */ foo(bar);\n"), [CommentStyle::Leading]);
+    }
+
+    #[test]
+    fn mixed_comment_sits_between_code_on_both_sides() {
+        assert_eq!(styles("foo(/* x */ bar);\n"), [CommentStyle::Mixed]);
+    }
+
+    #[test]
+    fn blank_line_comment_is_preceded_by_two_or_more_empty_lines() {
+        assert_eq!(styles("foo();\n\n\n// section\nbar();\n"), [CommentStyle::BlankLine]);
+    }
+}
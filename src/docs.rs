@@ -0,0 +1,49 @@
+// docs.rs
+//! Strips Javadoc/doc-comment decoration, leaving the clean body text.
+//!
+//! Java distinguishes `/** ... */` and `///`-style documentation comments
+//! from ordinary comments (see `TokenKind::DocComment`). This implements
+//! the standard "strip the `*` decoration" transform so consumers don't
+//! each have to re-implement it.
+
+/// Strip the comment delimiters and per-line decoration from a doc
+/// comment's raw token text, preserving paragraph structure.
+pub fn strip_decoration(text: &str) -> String {
+    if let Some(body) = text.strip_prefix("/**") {
+        let body = body.strip_suffix("*/").unwrap_or(body);
+        // A multi-line comment's body starts right after `/**` with the
+        // newline that ends that first line; without dropping it,
+        // `.lines()` would yield a spurious empty first element.
+        let body = body.strip_prefix('\n').unwrap_or(body);
+        body.lines().map(strip_line_decoration).collect::<Vec<_>>().join("\n")
+    } else if let Some(body) = text.strip_prefix("///") {
+        strip_line_decoration(body).to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+/// Strip leading whitespace, an optional `*`, and one following space from
+/// a single line of a doc comment's body.
+fn strip_line_decoration(line: &str) -> &str {
+    let trimmed = line.trim_start();
+    match trimmed.strip_prefix('*') {
+        Some(rest) => rest.strip_prefix(' ').unwrap_or(rest),
+        None => trimmed.strip_prefix(' ').unwrap_or(trimmed),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_line_doc_comment_has_no_spurious_blank_lines() {
+        assert_eq!(strip_decoration("/** x */"), "x ");
+    }
+
+    #[test]
+    fn multi_line_doc_comment_does_not_start_with_a_blank_line() {
+        assert_eq!(strip_decoration("/**\n * body\n */"), "body\n");
+    }
+}
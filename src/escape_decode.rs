@@ -0,0 +1,94 @@
+// escape_decode.rs
+//! The `\uXXXX` decoding rule itself, shared by the whole-file
+//! ([`crate::unicode_escape`]) and streaming ([`crate::buffered_char_reader`])
+//! translation passes so the escape-validity rules — including Java's
+//! surrogate-pair encoding of supplementary characters like emoji — can't
+//! drift between the two.
+
+/// Try to decode a `\uXXXX` escape (or, for a supplementary character
+/// outside the Basic Multilingual Plane, a `\uXXXX\uXXXX` surrogate pair)
+/// whose opening backslash has already been consumed. `rest` is
+/// everything after that backslash.
+///
+/// Returns `Some((char, consumed))` on success, where `consumed` is the
+/// number of bytes of `rest` that were part of the escape. Returns `None`
+/// if `rest` isn't a well-formed escape; use [`looks_like_escape`] to tell
+/// "not an escape at all" apart from "malformed".
+pub fn decode(rest: &str) -> Option<(char, usize)> {
+    let (hex, consumed) = take_u_run_and_hex(rest)?;
+    let code_point = u32::from_str_radix(hex, 16).ok()?;
+
+    if let Some(c) = char::from_u32(code_point) {
+        return Some((c, consumed));
+    }
+
+    // A lone surrogate half isn't a valid scalar value on its own, but
+    // Java writes supplementary characters as a high surrogate escape
+    // immediately followed by a low surrogate escape.
+    if (0xD800..=0xDBFF).contains(&code_point) {
+        let low_rest = rest[consumed..].strip_prefix('\\')?;
+        let (low_hex, low_consumed) = take_u_run_and_hex(low_rest)?;
+        let low = u32::from_str_radix(low_hex, 16).ok()?;
+        if (0xDC00..=0xDFFF).contains(&low) {
+            let combined = 0x10000 + (code_point - 0xD800) * 0x400 + (low - 0xDC00);
+            let c = char::from_u32(combined)?;
+            return Some((c, consumed + 1 + low_consumed));
+        }
+    }
+    None
+}
+
+/// Does `rest` start with at least one `u`? A failed [`decode`] only
+/// counts as a *malformed* escape (as opposed to "no escape here at all")
+/// when this is true.
+pub fn looks_like_escape(rest: &str) -> bool {
+    rest.starts_with('u')
+}
+
+/// Consume a run of one-or-more `u`s followed by exactly four hex
+/// digits, returning the hex digits and the total bytes consumed.
+fn take_u_run_and_hex(rest: &str) -> Option<(&str, usize)> {
+    let u_end = rest.find(|c| c != 'u').unwrap_or(rest.len());
+    if u_end == 0 {
+        return None;
+    }
+    let hex = rest.get(u_end..u_end + 4)?;
+    hex.chars().all(|h| h.is_ascii_hexdigit()).then_some((hex, u_end + 4))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_a_simple_escape() {
+        assert_eq!(decode("u0041"), Some(('A', 5)));
+    }
+
+    #[test]
+    fn decodes_a_repeated_u_opener() {
+        assert_eq!(decode("uu0041"), Some(('A', 6)));
+    }
+
+    #[test]
+    fn rejects_bad_hex_digits() {
+        assert_eq!(decode("u00zz"), None);
+    }
+
+    #[test]
+    fn rejects_a_lone_high_surrogate() {
+        assert_eq!(decode("uD83D"), None);
+    }
+
+    #[test]
+    fn decodes_a_surrogate_pair_into_a_supplementary_character() {
+        // U+1F600 GRINNING FACE, encoded as the surrogate pair Java source
+        // would actually contain.
+        assert_eq!(decode("uD83D\\uDE00"), Some(('\u{1F600}', 11)));
+    }
+
+    #[test]
+    fn does_not_look_like_an_escape_without_a_u() {
+        assert!(!looks_like_escape("0041"));
+    }
+}
@@ -1,8 +1,31 @@
 use clap::Parser;
-use std::fs;
+use std::fs::{self, File};
 use std::path::PathBuf;
 use std::io::{self, Read};
 
+mod buffered_char_reader;
+mod classifier;
+mod docs;
+mod escape_decode;
+mod output_writer;
+mod records;
+mod stream;
+mod tokenizer;
+mod unicode_escape;
+
+use classifier::classify;
+use tokenizer::{tokenize, TokenKind};
+use unicode_escape::Translated;
+
+/// Output format for the default (non-`--classify`) run.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum Format {
+    /// Masked source text (the historical default).
+    Text,
+    /// A JSON array of structured comment/text-block records.
+    Json,
+}
+
 /// A simple Java comment and optional string extractor
 #[derive(Parser)]
 struct Args {
@@ -12,262 +35,146 @@ struct Args {
     /// Preserve string contents (otherwise mask with whitespace)
     #[arg(long)]
     preserve_strings: bool,
-}
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum State {
-    Normal,
-    LineComment,
-    BlockComment,
-    StringLiteral,
-    TextBlockLiteral,
-    CharLiteral,
+    /// Instead of masked source, print each comment's line:column and its
+    /// classification (isolated / trailing / leading / mixed / blank-line)
+    #[arg(long, conflicts_with_all = ["doc_only", "format"])]
+    classify: bool,
+
+    /// Output format: masked source text, or structured JSON comment records
+    /// (conflicts with --classify and --doc-only, which have their own
+    /// output shapes)
+    #[arg(long, value_enum, conflicts_with = "doc_only")]
+    format: Option<Format>,
+
+    /// Print only Javadoc/doc comments, with decoration stripped
+    #[arg(long)]
+    doc_only: bool,
+
+    /// Don't decode Java `\uXXXX` unicode escapes before lexing (treat
+    /// them as literal text, for non-compliant input)
+    #[arg(long)]
+    no_unicode_escapes: bool,
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args = Args::parse();
-    let input = match args.input.as_ref().and_then(|p| p.to_str()).filter(|s| *s != "-") {
-        Some(path_str) => fs::read_to_string(path_str)?,
-        None => {
-            let mut buffer = String::new();
-            io::stdin().read_to_string(&mut buffer)?;
-            buffer
+    let decode_unicode_escapes = !args.no_unicode_escapes;
+
+    // `--classify`, `--doc-only`, and `--format json` all need the whole
+    // source in memory to work with typed tokens; the plain masking path
+    // doesn't, so it streams straight from the input instead.
+    if args.classify || args.doc_only || args.format == Some(Format::Json) {
+        let original = read_to_string(&args.input)?;
+        let translated = decode_unicode_escapes.then(|| unicode_escape::translate(&original));
+        if let Some(t) = &translated {
+            warn_on_malformed_escapes(t.malformed_escapes);
+        }
+        let input = translated.as_ref().map_or(original.as_str(), |t| t.text.as_str());
+
+        if args.classify {
+            print!("{}", classify_report(input, &original, translated.as_ref()));
+        } else if args.doc_only {
+            print!("{}", doc_only_report(input));
+        } else {
+            let mut comments = records::collect(input);
+            if let Some(t) = &translated {
+                remap_records(&mut comments, t, &original);
+            }
+            println!("{}", records::to_json(&comments));
+        }
+        return Ok(());
+    }
+
+    let stdout = io::stdout();
+    let malformed = match args.input.as_ref().and_then(|p| p.to_str()).filter(|s| *s != "-") {
+        Some(path_str) => {
+            stream::process_stream(File::open(path_str)?, stdout.lock(), args.preserve_strings, decode_unicode_escapes)?
         }
+        None => stream::process_stream(io::stdin().lock(), stdout.lock(), args.preserve_strings, decode_unicode_escapes)?,
     };
-    let output = process(&input, args.preserve_strings);
-    println!("{}", output);
+    warn_on_malformed_escapes(malformed);
     Ok(())
 }
 
-fn process(input: &str, preserve_strings: bool) -> String {
-    let mut output = String::with_capacity(input.len());
-    let mut chars = input.chars().peekable();
-    let mut state = State::Normal;
-
-    while let Some(c) = chars.next() {
-        match state {
-            State::Normal => {
-                match c {
-                    '/' => {
-                        match chars.peek() {
-                            Some('/') => {
-                                chars.next();
-                                output.push_str("  ");
-                                state = State::LineComment;
-                            }
-                            Some('*') => {
-                                chars.next();
-                                output.push_str("  ");
-                                while let Some('*') = chars.peek() {
-                                    chars.next();
-                                    output.push(' ');
-                                }
-                                state = State::BlockComment;
-                                if maybe_close_block_comment(&mut chars) {
-                                    state = State::Normal;
-                                }
-                            }
-                            _ => {
-                                output.push(' ');
-                            }
-                        }
-                    }
-                    '"' => {
-                        if let Some(&next1) = chars.peek() {
-                            if next1 == '"' {
-                                chars.next(); // consume second quote
-                                if let Some(&next2) = chars.peek() {
-                                    if next2 == '"' {
-                                        chars.next(); // consume third quote
-                                        // It's a triple quote: start text block
-                                        output.push(' ');
-                                        output.push(' ');
-                                        output.push(' ');
-                                        state = State::TextBlockLiteral;
-                                        continue;
-                                    } else {
-                                        // Only two quotes: empty string!
-                                        output.push(' ');
-                                        output.push(' ');
-                                        // Immediately back to normal
-                                        state = State::Normal;
-                                        continue;
-                                    }
-                                } else {
-                                    // Second quote but then EOF — weird, but same, treat as empty string
-                                    output.push(' ');
-                                    output.push(' ');
-                                    state = State::Normal;
-                                    continue;
-                                }
-                            }
-                        }
-                        // Only one quote
-                        output.push(' ');
-                        state = State::StringLiteral;
-                    }                    
-                    '\'' => {
-                        output.push(' ');
-                        state = State::CharLiteral;
-                    }
-                    '\n' => {
-                        output.push('\n');
-                    }
-                    _ => {
-                        output.push(' ');
-                    }
-                }
-            }
-            State::LineComment => {
-                match c {
-                    '\n' => {
-                        output.push('\n');
-                        state = State::Normal;
-                    }
-                    _ => output.push(c),
-                }
-            }
-            State::BlockComment => {
-                match c {
-                    '*' => {
-                        if let Some('/') = chars.peek() {
-                            chars.next();
-                            output.push(' ');
-                            output.push(' ');
-                            state = State::Normal;
-                            continue;
-                        } else {
-                            output.push('*');
-                        }
-                    }
-                    '\n' => {
-                        output.push('\n');
-                        if maybe_close_block_comment(&mut chars) {
-                            state = State::Normal;
-                            continue;
-                        }
-                    }
-                    _ => output.push(c),
-                }
-            }
-            State::StringLiteral => {
-                match c {
-                    '\\' => {
-                        if let Some(escaped) = chars.next() {
-                            if preserve_strings {
-                                output.push(escaped);
-                            } else {
-                                output.push(' ');
-                            }
-                        }
-                    }
-                    '"' => {
-                        output.push(' ');
-                        state = State::Normal;
-                        continue;
-                    }
-                    '\n' => {
-                        output.push('\n');
-                        state = State::Normal;
-                    }
-                    _ => {
-                        if preserve_strings {
-                            output.push(c);
-                        } else {
-                            output.push(' ');
-                        }
-                    }
-                }
-            }
-            State::TextBlockLiteral => {
-                match c {
-                    '"' => {
-                        if let Some(&next1) = chars.peek() {
-                            if next1 == '"' {
-                                chars.next();
-                                if let Some(&next2) = chars.peek() {
-                                    if next2 == '"' {
-                                        chars.next();
-                                        // Closing triple quote detected
-                                        // Mask closing """ always
-                                        output.push(' ');
-                                        output.push(' ');
-                                        output.push(' ');
-                                        state = State::Normal;
-                                        continue;
-                                    }
-                                }
-                            }
-                        }
-                        // A lone quote inside a text block? Just treat it as content
-                        if preserve_strings {
-                            output.push('"');
-                        } else {
-                            output.push(' ');
-                        }
-                    }
-                    '\\' => {
-                        if let Some(escaped) = chars.next() {
-                            if preserve_strings {
-                                output.push(escaped);
-                            } else {
-                                output.push(' ');
-                            }
-                        }
-                    }
-                    '\n' => {
-                        output.push('\n');
-                    }
-                    _ => {
-                        if preserve_strings {
-                            output.push(c);
-                        } else {
-                            output.push(' ');
-                        }
-                    }
-                }
-            }
-            State::CharLiteral => {
-                match c {
-                    '\\' => {
-                        output.push(' ');
-                        if let Some(_) = chars.next() {
-                            output.push(' ');
-                        }
-                    }
-                    '\'' => {
-                        output.push(' ');
-                        state = State::Normal;
-                    }
-                    '\n' => {
-                        output.push('\n');
-                        state = State::Normal;
-                    }
-                    _ => {
-                        output.push(' ');
-                    }
-                }
-            }
+/// Read the whole input (file or stdin) into a `String` for the modes that
+/// need random access to it.
+fn read_to_string(input: &Option<PathBuf>) -> io::Result<String> {
+    match input.as_ref().and_then(|p| p.to_str()).filter(|s| *s != "-") {
+        Some(path_str) => fs::read_to_string(path_str),
+        None => {
+            let mut buffer = String::new();
+            io::stdin().read_to_string(&mut buffer)?;
+            Ok(buffer)
         }
     }
+}
 
-    output
+fn warn_on_malformed_escapes(count: usize) {
+    if count > 0 {
+        eprintln!("warning: {} malformed \\u escape(s) found; treated as literal text", count);
+    }
+}
+
+/// Render one `line:column kind [style]` entry per comment in `input`
+/// (the text actually tokenized). Positions are reported against
+/// `original` — the file as the user has it — via `translated`'s offset
+/// map, in case `\uXXXX` decoding shifted things around.
+fn classify_report(input: &str, original: &str, translated: Option<&Translated>) -> String {
+    let tokens: Vec<_> = tokenize(input).collect();
+    let mut report = String::new();
+    for (token, style) in classify(input, &tokens) {
+        let original_start = translated.map_or(token.start, |t| t.original_offset(token.start));
+        let (line, column) = line_col(original, original_start);
+        report.push_str(&format!("{}:{} {:?} [{:?}]\n", line, column, token.kind, style));
+    }
+    report
 }
 
+/// Rewrite each record's `offset`/`length`/`line`/`column` (computed
+/// against the `\uXXXX`-decoded text) to refer to `original` instead.
+///
+/// `text` is deliberately left untouched: it's the comment's decoded body,
+/// while the remapped fields locate that body's (possibly differently
+/// sized) encoded form in `original`. See the note on
+/// [`records::CommentRecord`](records::CommentRecord).
+fn remap_records(records: &mut [records::CommentRecord], translated: &Translated, original: &str) {
+    for r in records.iter_mut() {
+        let start = translated.original_offset(r.offset);
+        let end = translated.original_offset(r.offset + r.length);
+        let (line, column) = line_col(original, start);
+        r.offset = start;
+        r.length = end - start;
+        r.line = line;
+        r.column = column;
+    }
+}
 
-/// Helper function to check and handle block comment closure after a newline
-fn maybe_close_block_comment<I: Iterator<Item = char>>(chars: &mut std::iter::Peekable<I>) -> bool {
-    while let Some(' ' | '\t') = chars.peek() {
-        chars.next();
+/// Print the decoration-stripped body of every doc comment in `input`,
+/// separated by blank lines.
+fn doc_only_report(input: &str) -> String {
+    let mut report = String::new();
+    for token in tokenize(input) {
+        if token.kind == TokenKind::DocComment {
+            report.push_str(&docs::strip_decoration(token.text(input)));
+            report.push_str("\n\n");
+        }
     }
-    if let Some('*') = chars.peek() {
-        chars.next();
-        if let Some('/') = chars.peek() {
-            chars.next();
-            return true;
-        } else if let Some(' ') = chars.peek() {
-            chars.next();
+    report
+}
+
+/// 1-based (line, column) of byte offset `pos` in `input`, counting
+/// columns in chars and resetting at each `\n`.
+fn line_col(input: &str, pos: usize) -> (usize, usize) {
+    let mut line = 1;
+    let mut column = 1;
+    for c in input[..pos].chars() {
+        if c == '\n' {
+            line += 1;
+            column = 1;
+        } else {
+            column += 1;
         }
     }
-    false
+    (line, column)
 }
@@ -35,7 +35,6 @@ impl<W: Write> OutputWriter<W> {
         Ok(())
     }
 
-    #[allow(dead_code)]
     pub fn flush(&mut self) -> io::Result<()> {
         self.writer.flush()
     }
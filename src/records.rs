@@ -0,0 +1,147 @@
+// records.rs
+//! Turns the token stream into structured comment records suitable for
+//! `--format json`, so the tool can be piped into `jq` or any other
+//! processor instead of forcing consumers to diff whitespace.
+
+use crate::tokenizer::{tokenize, TokenKind};
+
+/// One comment (or text block), ready to serialize.
+///
+/// As [`collect`] returns them, `line`/`column`/`offset`/`length` describe
+/// where the token sits in the source as tokenized. When the caller runs `\uXXXX` translation
+/// first (the default; see [`crate::unicode_escape`]) and then remaps
+/// these onto the original file, `text` stops being a verbatim slice of
+/// `original[offset..offset+length]`: `text` is the comment's *decoded*
+/// body, while the position fields describe where the (longer) *encoded*
+/// escape sequence sits in the file the user actually has open.
+/// Positions are for locating the comment; `text` is for reading it.
+#[derive(Debug, Clone)]
+pub struct CommentRecord {
+    pub kind: &'static str,
+    pub line: usize,
+    pub column: usize,
+    pub offset: usize,
+    pub length: usize,
+    pub text: String,
+}
+
+/// Collect one [`CommentRecord`] per line/block/doc comment and text block
+/// in `input`, in source order. Line and column are 1-based and computed
+/// by counting newlines while walking the token stream.
+pub fn collect(input: &str) -> Vec<CommentRecord> {
+    let mut records = Vec::new();
+    let mut line = 1;
+    let mut column = 1;
+    let mut pos = 0;
+
+    for token in tokenize(input) {
+        advance(input, pos, token.start, &mut line, &mut column);
+        pos = token.start;
+
+        if is_recorded(token.kind) {
+            let text = token.text(input);
+            records.push(CommentRecord {
+                kind: kind_name(token.kind),
+                line,
+                column,
+                offset: token.start,
+                length: token.len,
+                text: strip_delimiters(token.kind, text).to_string(),
+            });
+        }
+
+        advance(input, pos, token.start + token.len, &mut line, &mut column);
+        pos = token.start + token.len;
+    }
+
+    records
+}
+
+fn is_recorded(kind: TokenKind) -> bool {
+    matches!(
+        kind,
+        TokenKind::LineComment | TokenKind::BlockComment | TokenKind::DocComment | TokenKind::TextBlock
+    )
+}
+
+fn kind_name(kind: TokenKind) -> &'static str {
+    match kind {
+        TokenKind::LineComment => "line",
+        TokenKind::BlockComment => "block",
+        TokenKind::DocComment => "doc",
+        TokenKind::TextBlock => "textblock",
+        TokenKind::Code | TokenKind::StringLiteral | TokenKind::CharLiteral => "code",
+    }
+}
+
+/// Strip the comment/text-block delimiters off `text`, leaving the body.
+fn strip_delimiters(kind: TokenKind, text: &str) -> &str {
+    match kind {
+        TokenKind::LineComment => text.strip_prefix("//").unwrap_or(text),
+        TokenKind::DocComment => {
+            if let Some(rest) = text.strip_prefix("/**") {
+                rest.strip_suffix("*/").unwrap_or(rest)
+            } else {
+                text.strip_prefix("///").unwrap_or(text)
+            }
+        }
+        TokenKind::BlockComment => {
+            let rest = text.strip_prefix("/*").unwrap_or(text);
+            rest.strip_suffix("*/").unwrap_or(rest)
+        }
+        TokenKind::TextBlock => {
+            let rest = text.strip_prefix("\"\"\"").unwrap_or(text);
+            rest.strip_suffix("\"\"\"").unwrap_or(rest)
+        }
+        TokenKind::Code | TokenKind::StringLiteral | TokenKind::CharLiteral => text,
+    }
+}
+
+/// Advance `line`/`column` by counting the chars of `input[from..to]`.
+fn advance(input: &str, from: usize, to: usize, line: &mut usize, column: &mut usize) {
+    for c in input[from..to].chars() {
+        if c == '\n' {
+            *line += 1;
+            *column = 1;
+        } else {
+            *column += 1;
+        }
+    }
+}
+
+/// Serialize `records` as a JSON array.
+pub fn to_json(records: &[CommentRecord]) -> String {
+    let mut out = String::from("[\n");
+    for (i, r) in records.iter().enumerate() {
+        if i > 0 {
+            out.push_str(",\n");
+        }
+        out.push_str(&format!(
+            "  {{\"kind\": \"{}\", \"line\": {}, \"column\": {}, \"offset\": {}, \"length\": {}, \"text\": \"{}\"}}",
+            r.kind,
+            r.line,
+            r.column,
+            r.offset,
+            r.length,
+            json_escape(&r.text)
+        ));
+    }
+    out.push_str("\n]");
+    out
+}
+
+fn json_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out
+}
@@ -0,0 +1,347 @@
+// stream.rs
+//! True streaming mask pipeline: pulls characters one at a time from a
+//! [`BufferedCharReader`] and pushes output through an [`OutputWriter`], so
+//! memory use stays bounded no matter how large the input is.
+//!
+//! This is a second, independent Java comment/string scanner alongside
+//! [`crate::tokenizer`] — it can't reuse `tokenize()` because that walks a
+//! complete in-memory `&str`, while this drives a one-char-at-a-time
+//! [`BufferedCharReader`]. Opening a block comment whose extra `*`s run
+//! straight into its own `*/` with no content in between (`/**/`,
+//! `/***/`, ...) ported `tokenizer::block_comment`'s closing rule — a star
+//! immediately followed by `/` always closes the comment, decoration or
+//! not — so the two scanners agree on every such input; `parity_tests`
+//! below checks that agreement directly.
+
+use std::io::{self, Read, Write};
+
+use crate::buffered_char_reader::BufferedCharReader;
+use crate::output_writer::OutputWriter;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Normal,
+    LineComment,
+    BlockComment,
+    StringLiteral,
+    TextBlockLiteral,
+    CharLiteral,
+}
+
+/// Stream-mask Java source read from `reader`, writing the masked result
+/// to `writer` as it goes and flushing once `reader` is exhausted.
+///
+/// When `decode_unicode_escapes` is set, `\uXXXX` escapes are decoded
+/// transparently as the reader sees them (so e.g. two `\uXXXX` escapes of
+/// `/`, hex 2F, in a row open a real line comment), matching `javac`'s pre-lexing translation pass.
+/// Returns the number of malformed escapes encountered.
+pub fn process_stream<R: Read, W: Write>(
+    reader: R,
+    writer: W,
+    preserve_strings: bool,
+    decode_unicode_escapes: bool,
+) -> io::Result<usize> {
+    let mut input = BufferedCharReader::with_unicode_escapes(reader, decode_unicode_escapes);
+    let mut out = OutputWriter::new(writer);
+    let mut state = State::Normal;
+
+    while let Some(c) = input.next_char()? {
+        match state {
+            State::Normal => match c {
+                '/' => match input.peek_char()? {
+                    Some('/') => {
+                        input.next_char()?;
+                        out.write_n_spaces(2)?;
+                        state = State::LineComment;
+                    }
+                    Some('*') => {
+                        input.next_char()?;
+                        out.write_n_spaces(2)?;
+                        state = if open_block_comment_star_run(&mut input, &mut out)? {
+                            State::Normal
+                        } else {
+                            State::BlockComment
+                        };
+                    }
+                    _ => out.write_char(' ')?,
+                },
+                '"' => {
+                    if input.peek_char()? == Some('"') {
+                        input.next_char()?;
+                        if input.peek_char()? == Some('"') {
+                            input.next_char()?;
+                            // Triple quote: start a text block.
+                            out.write_n_spaces(3)?;
+                            state = State::TextBlockLiteral;
+                        } else {
+                            // Two quotes only: an empty string.
+                            out.write_n_spaces(2)?;
+                        }
+                    } else {
+                        out.write_char(' ')?;
+                        state = State::StringLiteral;
+                    }
+                }
+                '\'' => {
+                    out.write_char(' ')?;
+                    state = State::CharLiteral;
+                }
+                '\n' => out.write_char('\n')?,
+                _ => out.write_char(' ')?,
+            },
+            State::LineComment => match c {
+                '\n' => {
+                    out.write_char('\n')?;
+                    state = State::Normal;
+                }
+                _ => out.write_char(c)?,
+            },
+            State::BlockComment => match c {
+                '*' => {
+                    if input.peek_char()? == Some('/') {
+                        input.next_char()?;
+                        out.write_n_spaces(2)?;
+                        state = State::Normal;
+                    } else {
+                        out.write_char('*')?;
+                    }
+                }
+                '\n' => {
+                    out.write_char('\n')?;
+                    if maybe_close_block_comment(&mut input)? {
+                        state = State::Normal;
+                    }
+                }
+                _ => out.write_char(c)?,
+            },
+            State::StringLiteral => match c {
+                '\\' => {
+                    if let Some(escaped) = input.next_char()? {
+                        if preserve_strings {
+                            out.write_char(escaped)?;
+                        } else {
+                            out.write_char(' ')?;
+                        }
+                    }
+                }
+                '"' => {
+                    out.write_char(' ')?;
+                    state = State::Normal;
+                }
+                '\n' => {
+                    out.write_char('\n')?;
+                    state = State::Normal;
+                }
+                _ => {
+                    if preserve_strings {
+                        out.write_char(c)?;
+                    } else {
+                        out.write_char(' ')?;
+                    }
+                }
+            },
+            State::TextBlockLiteral => match c {
+                '"' if input.peek_char()? == Some('"') => {
+                    input.next_char()?;
+                    if input.peek_char()? == Some('"') {
+                        input.next_char()?;
+                        out.write_n_spaces(3)?;
+                        state = State::Normal;
+                    } else if preserve_strings {
+                        out.write_str("\"\"")?;
+                    } else {
+                        out.write_n_spaces(2)?;
+                    }
+                }
+                '"' => {
+                    if preserve_strings {
+                        out.write_char('"')?;
+                    } else {
+                        out.write_char(' ')?;
+                    }
+                }
+                '\\' => {
+                    if let Some(escaped) = input.next_char()? {
+                        if preserve_strings {
+                            out.write_char(escaped)?;
+                        } else {
+                            out.write_char(' ')?;
+                        }
+                    }
+                }
+                '\n' => out.write_char('\n')?,
+                _ => {
+                    if preserve_strings {
+                        out.write_char(c)?;
+                    } else {
+                        out.write_char(' ')?;
+                    }
+                }
+            },
+            State::CharLiteral => match c {
+                '\\' => {
+                    out.write_char(' ')?;
+                    if input.next_char()?.is_some() {
+                        out.write_char(' ')?;
+                    }
+                }
+                '\'' => {
+                    out.write_char(' ')?;
+                    state = State::Normal;
+                }
+                '\n' => {
+                    out.write_char('\n')?;
+                    state = State::Normal;
+                }
+                _ => out.write_char(' ')?,
+            },
+        }
+    }
+
+    out.flush()?;
+    Ok(input.malformed_escape_count())
+}
+
+/// After the two chars that open a block comment (`/*`) have already been
+/// masked, consume any run of additional `*`s immediately following,
+/// mirroring `tokenizer::block_comment`'s rule: a star immediately
+/// followed by `/` always closes the comment, decoration or not — so an
+/// all-star comment like `/**/` or `/***/` can't leak its closing `/` as
+/// literal output. Returns whether the comment closed.
+fn open_block_comment_star_run<R: Read, W: Write>(
+    input: &mut BufferedCharReader<R>,
+    out: &mut OutputWriter<W>,
+) -> io::Result<bool> {
+    while let Some('*') = input.peek_char()? {
+        input.next_char()?;
+        if input.peek_char()? == Some('/') {
+            input.next_char()?;
+            out.write_n_spaces(2)?;
+            return Ok(true);
+        }
+        out.write_char(' ')?;
+    }
+    maybe_close_block_comment(input)
+}
+
+/// After a newline inside a block comment (and right after its opener),
+/// swallow any spaces and tabs, then a leading `*` (and the one space after
+/// it) used to align continuation lines, and report whether that `*` turned
+/// out to close the comment (`*/`). Swallowed chars are not written out.
+fn maybe_close_block_comment<R: Read>(input: &mut BufferedCharReader<R>) -> io::Result<bool> {
+    while let Some(' ' | '\t') = input.peek_char()? {
+        input.next_char()?;
+    }
+    if let Some('*') = input.peek_char()? {
+        input.next_char()?;
+        if let Some('/') = input.peek_char()? {
+            input.next_char()?;
+            return Ok(true);
+        } else if let Some(' ') = input.peek_char()? {
+            input.next_char()?;
+        }
+    }
+    Ok(false)
+}
+
+/// Convenience wrapper over [`process_stream`] for callers that already
+/// have the whole input in memory.
+#[allow(dead_code)]
+pub fn process_str(input: &str, preserve_strings: bool) -> String {
+    let mut buf = Vec::new();
+    process_stream(input.as_bytes(), &mut buf, preserve_strings, true)
+        .expect("writing to an in-memory Vec<u8> cannot fail");
+    String::from_utf8(buf).expect("output only ever copies or masks input chars, so it stays valid UTF-8")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_block_comment_closes_and_masks_fully() {
+        assert_eq!(process_str("/**/x", true), "     ");
+    }
+
+    #[test]
+    fn triple_star_banner_closes_and_masks_fully() {
+        assert_eq!(process_str("/***/x", true), "      ");
+    }
+
+    #[test]
+    fn an_all_star_comment_does_not_swallow_the_rest_of_the_file() {
+        // The exact regression this fix closes: an empty comment used to
+        // leave the scanner stuck in `BlockComment`, so everything after
+        // it — including string contents the tool exists to mask — was
+        // emitted verbatim instead of masked.
+        let masked = process_str(
+            r#"int secret = 42; /**/ String password = "hunter2"; int more = 7;"#,
+            false,
+        );
+        assert!(!masked.contains("hunter2"), "leaked string content: {masked:?}");
+        assert!(!masked.contains("secret") && !masked.contains("more"), "leaked code: {masked:?}");
+    }
+
+    #[test]
+    fn quadruple_slash_banner_masks_as_an_ordinary_line_comment() {
+        // Unlike `tokenizer`'s doc/non-doc distinction, the mask path only
+        // cares about the opening `//`; everything after is comment
+        // content and is preserved verbatim regardless of extra `/`s.
+        assert_eq!(process_str("////x", true), "  //x");
+    }
+
+    #[test]
+    fn doc_and_plain_block_comments_mask_identically() {
+        assert_eq!(process_str("/** doc */x", true), "   doc    ");
+        assert_eq!(process_str("/* plain */x", true), "  plain    ");
+    }
+}
+
+#[cfg(test)]
+mod parity_tests {
+    use super::*;
+    use crate::tokenizer::{tokenize, TokenKind};
+
+    /// For an all-star block comment with any number of interior stars,
+    /// `tokenizer` and the streaming masker must agree that it's a single,
+    /// fully-closed comment ending at the first `*/` — not leak any of its
+    /// `*`/`/` punctuation into the code that follows.
+    #[test]
+    fn stream_closes_every_all_star_comment_tokenizer_closes() {
+        for stars in 0..=4 {
+            let case = format!("/*{}*/x", "*".repeat(stars));
+            let tokens: Vec<_> = tokenize(&case).collect();
+            assert_eq!(tokens.len(), 2, "case {case:?}: {tokens:?}");
+            assert_eq!(tokens[0].kind, TokenKind::BlockComment, "case {case:?}");
+            assert_eq!(tokens[0].len, case.len() - 1, "case {case:?}: tokenizer should close at the first */");
+            assert_eq!(tokens[1].kind, TokenKind::Code, "case {case:?}");
+
+            let masked = process_str(&case, true);
+            assert_eq!(masked, " ".repeat(case.len()), "case {case:?}: comment and trailing code should all mask to spaces");
+        }
+    }
+
+    /// The block-comment bug this module fixed — a delimiter run closing
+    /// immediately, with the scanner never leaving its special state — is
+    /// a shape `tokenizer` also has to get right for text blocks (`""""""`)
+    /// and char literals (`''`). Check the streaming masker agrees with
+    /// `tokenizer` on those too, so a future edit can't reintroduce the
+    /// same class of bug in a sibling scanner without a test noticing.
+    #[test]
+    fn stream_fully_masks_empty_text_blocks_and_char_literals_like_tokenizer() {
+        for (case, expected_kind) in [
+            (r#"""""""x"#, TokenKind::TextBlock),
+            (r#""""a"""x"#, TokenKind::TextBlock),
+            ("''x", TokenKind::CharLiteral),
+            ("'a'x", TokenKind::CharLiteral),
+        ] {
+            let tokens: Vec<_> = tokenize(case).collect();
+            assert_eq!(tokens[0].kind, expected_kind, "case {case:?}: {tokens:?}");
+            assert_eq!(tokens[1].kind, TokenKind::Code, "case {case:?}: {tokens:?}");
+
+            let masked = process_str(case, false);
+            assert_eq!(masked.len(), case.len(), "case {case:?}: masked output should be the same length as input");
+            assert!(masked.chars().all(|c| c == ' '), "case {case:?}: expected a full mask, got {masked:?}");
+        }
+    }
+}
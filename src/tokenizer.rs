@@ -0,0 +1,293 @@
+// tokenizer.rs
+//! Standalone Java lexer that yields typed spans over the input text.
+//!
+//! Unlike the old `process` function, `tokenize` never builds an output
+//! buffer and never masks anything: it just walks the `&str` once and
+//! reports what it found. Consumers (the CLI's `--classify`, `--doc-only`
+//! and `--format json` modes, or anything else that wants to embed this
+//! crate) decide what to do with each [`Token`]. The CLI's plain masking
+//! path does not use it; see [`crate::stream`].
+
+/// The kind of span a [`Token`] covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    /// Ordinary Java source text (not inside a comment, string, or char literal).
+    Code,
+    /// A `//` comment, not including the terminating newline.
+    LineComment,
+    /// A `/* ... */` comment that isn't a doc comment.
+    BlockComment,
+    /// A `/** ... */` or `///` documentation comment.
+    DocComment,
+    /// A `"..."` string literal.
+    StringLiteral,
+    /// A `'...'` char literal.
+    CharLiteral,
+    /// A `"""..."""` text block.
+    TextBlock,
+}
+
+impl TokenKind {
+    /// Is this one of the comment kinds (as opposed to code, a string, a
+    /// char literal, or a text block)?
+    pub fn is_comment(self) -> bool {
+        matches!(self, TokenKind::LineComment | TokenKind::BlockComment | TokenKind::DocComment)
+    }
+}
+
+/// Recoverable problems noticed while scanning a token.
+///
+/// Reported on the [`Token`] instead of panicking or silently masking the
+/// issue, so callers can decide how much they care.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TokenFlags(u8);
+
+impl TokenFlags {
+    pub const NONE: TokenFlags = TokenFlags(0);
+    /// The token ran into end-of-input before it was closed (block comment,
+    /// string or char literal, or text block missing its terminator).
+    pub const UNTERMINATED: TokenFlags = TokenFlags(1 << 0);
+    /// End-of-input was reached while still inside a string literal.
+    pub const EOF_IN_STRING: TokenFlags = TokenFlags(1 << 1);
+
+    #[allow(dead_code)]
+    pub fn contains(self, other: TokenFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    fn insert(&mut self, other: TokenFlags) {
+        self.0 |= other.0;
+    }
+}
+
+impl std::ops::BitOr for TokenFlags {
+    type Output = TokenFlags;
+
+    fn bitor(self, rhs: TokenFlags) -> TokenFlags {
+        TokenFlags(self.0 | rhs.0)
+    }
+}
+
+/// One lexical span: a `kind`, the byte range `start..start+len` into the
+/// original `&str`, and any recoverable `flags`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub start: usize,
+    pub len: usize,
+    pub flags: TokenFlags,
+}
+
+impl Token {
+    /// The original source text this token covers.
+    pub fn text<'a>(&self, input: &'a str) -> &'a str {
+        &input[self.start..self.start + self.len]
+    }
+}
+
+/// Lex `input` into a sequence of [`Token`]s covering the whole string.
+pub fn tokenize(input: &str) -> impl Iterator<Item = Token> + '_ {
+    Tokenizer { input, pos: 0 }
+}
+
+struct Tokenizer<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Tokenizer<'a> {
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.rest().chars().nth(offset)
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.rest().chars().next()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn make(&self, kind: TokenKind, start: usize, flags: TokenFlags) -> Token {
+        Token { kind, start, len: self.pos - start, flags }
+    }
+
+    fn code_run(&mut self, start: usize) -> Token {
+        loop {
+            match self.peek_at(0) {
+                None => break,
+                Some('/') if matches!(self.peek_at(1), Some('/') | Some('*')) => break,
+                Some('"') | Some('\'') => break,
+                _ => {
+                    self.bump();
+                }
+            }
+        }
+        self.make(TokenKind::Code, start, TokenFlags::NONE)
+    }
+
+    fn line_comment(&mut self, start: usize) -> Token {
+        self.bump(); // second '/'
+        let kind = if self.peek_at(0) == Some('/') && self.peek_at(1) != Some('/') {
+            self.bump();
+            TokenKind::DocComment
+        } else {
+            TokenKind::LineComment
+        };
+        while !matches!(self.peek_at(0), None | Some('\n')) {
+            self.bump();
+        }
+        self.make(kind, start, TokenFlags::NONE)
+    }
+
+    fn block_comment(&mut self, start: usize) -> Token {
+        self.bump(); // '*'
+        // `/**/` is an empty block comment (no content before `*/`), and
+        // `/***` is a decoration banner, not a doc comment: only exactly
+        // two stars followed by something else counts.
+        let is_doc =
+            self.peek_at(0) == Some('*') && !matches!(self.peek_at(1), Some('/') | Some('*'));
+        if is_doc {
+            self.bump();
+        }
+        let kind = if is_doc { TokenKind::DocComment } else { TokenKind::BlockComment };
+        let mut flags = TokenFlags::NONE;
+        loop {
+            match self.bump() {
+                None => {
+                    flags.insert(TokenFlags::UNTERMINATED);
+                    break;
+                }
+                Some('*') if self.peek_at(0) == Some('/') => {
+                    self.bump();
+                    break;
+                }
+                _ => {}
+            }
+        }
+        self.make(kind, start, flags)
+    }
+
+    fn string_literal(&mut self, start: usize) -> Token {
+        let mut flags = TokenFlags::NONE;
+        loop {
+            match self.bump() {
+                None => {
+                    flags.insert(TokenFlags::UNTERMINATED | TokenFlags::EOF_IN_STRING);
+                    break;
+                }
+                Some('\\') => {
+                    self.bump();
+                }
+                Some('"') | Some('\n') => break,
+                _ => {}
+            }
+        }
+        self.make(TokenKind::StringLiteral, start, flags)
+    }
+
+    fn text_block(&mut self, start: usize) -> Token {
+        self.bump(); // second '"'
+        self.bump(); // third '"'
+        let mut flags = TokenFlags::NONE;
+        loop {
+            match self.bump() {
+                None => {
+                    flags.insert(TokenFlags::UNTERMINATED);
+                    break;
+                }
+                Some('\\') => {
+                    self.bump();
+                }
+                Some('"') if self.peek_at(0) == Some('"') && self.peek_at(1) == Some('"') => {
+                    self.bump();
+                    self.bump();
+                    break;
+                }
+                _ => {}
+            }
+        }
+        self.make(TokenKind::TextBlock, start, flags)
+    }
+
+    fn char_literal(&mut self, start: usize) -> Token {
+        let mut flags = TokenFlags::NONE;
+        loop {
+            match self.bump() {
+                None => {
+                    flags.insert(TokenFlags::UNTERMINATED);
+                    break;
+                }
+                Some('\\') => {
+                    self.bump();
+                }
+                Some('\'') | Some('\n') => break,
+                _ => {}
+            }
+        }
+        self.make(TokenKind::CharLiteral, start, flags)
+    }
+}
+
+impl<'a> Iterator for Tokenizer<'a> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Token> {
+        let start = self.pos;
+        match self.bump()? {
+            '/' => match self.peek_at(0) {
+                Some('/') => Some(self.line_comment(start)),
+                Some('*') => Some(self.block_comment(start)),
+                _ => Some(self.code_run(start)),
+            },
+            '"' if self.peek_at(0) == Some('"') && self.peek_at(1) == Some('"') => {
+                Some(self.text_block(start))
+            }
+            '"' => Some(self.string_literal(start)),
+            '\'' => Some(self.char_literal(start)),
+            _ => Some(self.code_run(start)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(input: &str) -> Vec<(TokenKind, &str)> {
+        tokenize(input).map(|t| (t.kind, t.text(input))).collect()
+    }
+
+    #[test]
+    fn empty_block_comment_closes_immediately() {
+        assert_eq!(kinds("/**/x"), [(TokenKind::BlockComment, "/**/"), (TokenKind::Code, "x")]);
+    }
+
+    #[test]
+    fn triple_star_banner_is_not_a_doc_comment() {
+        assert_eq!(kinds("/***/x"), [(TokenKind::BlockComment, "/***/"), (TokenKind::Code, "x")]);
+    }
+
+    #[test]
+    fn double_star_with_content_is_a_doc_comment() {
+        assert_eq!(
+            kinds("/** doc */x"),
+            [(TokenKind::DocComment, "/** doc */"), (TokenKind::Code, "x")]
+        );
+    }
+
+    #[test]
+    fn quadruple_slash_banner_is_a_line_comment_not_doc() {
+        assert_eq!(kinds("////x"), [(TokenKind::LineComment, "////x")]);
+    }
+
+    #[test]
+    fn triple_slash_is_a_doc_comment() {
+        assert_eq!(
+            kinds("/// doc\nx"),
+            [(TokenKind::DocComment, "/// doc"), (TokenKind::Code, "\nx")]
+        );
+    }
+}
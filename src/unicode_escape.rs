@@ -0,0 +1,133 @@
+// unicode_escape.rs
+//! Java translates `\uXXXX` unicode escapes across the entire source
+//! *before* tokenizing ever starts, so two `\uXXXX` escapes of `/` (hex
+//! 2F) in a row lex as a line-comment opener and an escape of `"` (hex 22)
+//! as a string quote. This implements that
+//! pre-translation pass for callers working on a whole `&str` in memory
+//! (the streaming path integrates the same rule directly into
+//! [`crate::buffered_char_reader::BufferedCharReader`] instead).
+
+use crate::escape_decode;
+
+/// The result of translating `\uXXXX` escapes out of a source string.
+pub struct Translated {
+    pub text: String,
+    pub malformed_escapes: usize,
+    /// Maps each byte offset in `text` to the byte offset in the original
+    /// input it came from, so positions reported against `text` (line,
+    /// column, token spans) can be mapped back to the file the user
+    /// actually has open. One past the last byte maps to `original.len()`.
+    offset_map: Vec<usize>,
+}
+
+impl Translated {
+    /// Map a byte offset into the translated text back to the
+    /// corresponding byte offset in the original input.
+    pub fn original_offset(&self, translated_offset: usize) -> usize {
+        self.offset_map[translated_offset]
+    }
+}
+
+/// Decode Java `\uXXXX` escapes in `input` (including surrogate pairs, for
+/// supplementary characters like emoji — see [`crate::escape_decode`]).
+///
+/// An escape is only recognized after an *even* number of backslashes (an
+/// odd count means the backslash is itself escaped, e.g. `\\u` is a
+/// literal backslash followed by `u`). A malformed escape (one-or-more
+/// `u`s not followed by a valid hex sequence) is left as literal text
+/// rather than failing; it only bumps `malformed_escapes`.
+pub fn translate(input: &str) -> Translated {
+    let mut text = String::with_capacity(input.len());
+    let mut offset_map = Vec::with_capacity(input.len());
+    let mut malformed_escapes = 0;
+    let mut backslash_run = 0usize;
+    let mut pos = 0;
+
+    while pos < input.len() {
+        let c = input[pos..].chars().next().unwrap();
+        let c_len = c.len_utf8();
+
+        if c == '\\' && backslash_run.is_multiple_of(2) {
+            let rest = &input[pos + c_len..];
+            if let Some((decoded, consumed)) = escape_decode::decode(rest) {
+                text.push(decoded);
+                offset_map.extend(std::iter::repeat_n(pos, decoded.len_utf8()));
+                pos += c_len + consumed;
+                backslash_run = 0;
+                continue;
+            }
+            if escape_decode::looks_like_escape(rest) {
+                malformed_escapes += 1;
+            }
+        }
+
+        backslash_run = if c == '\\' { backslash_run + 1 } else { 0 };
+        text.push(c);
+        offset_map.extend(std::iter::repeat_n(pos, c_len));
+        pos += c_len;
+    }
+    offset_map.push(input.len());
+
+    Translated { text, malformed_escapes, offset_map }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_a_simple_escape() {
+        let t = translate("\\u0041");
+        assert_eq!(t.text, "A");
+        assert_eq!(t.malformed_escapes, 0);
+    }
+
+    #[test]
+    fn escaped_backslash_is_not_an_escape_opener() {
+        // `\\u0041` is a literal backslash followed by the letters `u0041`,
+        // not a decoded escape: the backslash before `u` is itself escaped.
+        let t = translate("\\\\u0041");
+        assert_eq!(t.text, "\\\\u0041");
+        assert_eq!(t.malformed_escapes, 0);
+    }
+
+    #[test]
+    fn three_backslashes_still_decode_the_trailing_escape() {
+        // Odd-but->1 runs: the first two backslashes pair off, leaving one
+        // real (unescaped) backslash to open the escape.
+        let t = translate("\\\\\\u0041");
+        assert_eq!(t.text, "\\\\A");
+        assert_eq!(t.malformed_escapes, 0);
+    }
+
+    #[test]
+    fn malformed_escape_is_left_as_literal_text() {
+        let t = translate("\\u00zz");
+        assert_eq!(t.text, "\\u00zz");
+        assert_eq!(t.malformed_escapes, 1);
+    }
+
+    #[test]
+    fn repeated_u_is_still_a_valid_opener() {
+        let t = translate("\\uu0041");
+        assert_eq!(t.text, "A");
+        assert_eq!(t.malformed_escapes, 0);
+    }
+
+    #[test]
+    fn offset_map_round_trips_through_a_decoded_escape() {
+        let original = "a\\u0041b";
+        let t = translate(original);
+        assert_eq!(t.text, "aAb");
+        // 'a' at translated offset 0 comes from original offset 0.
+        assert_eq!(t.original_offset(0), 0);
+        // The decoded 'A' at translated offset 1 comes from the start of
+        // the escape sequence (original offset 1, the backslash).
+        assert_eq!(t.original_offset(1), 1);
+        // 'b' at translated offset 2 comes from original offset 7.
+        assert_eq!(&original[7..8], "b");
+        assert_eq!(t.original_offset(2), 7);
+        // One past the last byte maps to original.len().
+        assert_eq!(t.original_offset(t.text.len()), original.len());
+    }
+}